@@ -0,0 +1,38 @@
+use clap::Parser;
+
+use crate::conversion::ConversionOverrides;
+
+/// Singer Parquet target
+#[derive(Parser, Debug)]
+#[command(version)]
+pub struct Args {
+    /// Directory to write each stream's Parquet file into
+    #[clap(short, long)]
+    pub output_dir: String,
+
+    /// Number of records to buffer before flushing a row group
+    #[clap(short, long, default_value_t = 10_000)]
+    pub batch_size: usize,
+
+    /// Fixed-UTC `strftime`-style format to parse `date-time` fields with, overriding
+    /// the RFC3339 default
+    #[clap(long)]
+    pub datetime_format: Option<String>,
+
+    /// Timezone-aware `strftime`-style format to parse `date-time` fields with
+    #[clap(long)]
+    pub datetime_format_tz: Option<String>,
+
+    /// Emit one JSON log record per row-group flush to stderr
+    #[clap(long)]
+    pub json_logging: bool,
+}
+
+impl Args {
+    pub fn conversion_overrides(&self) -> ConversionOverrides {
+        ConversionOverrides {
+            datetime_format: self.datetime_format.clone(),
+            datetime_format_tz: self.datetime_format_tz.clone(),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ConversionError {
+    #[error("unknown conversion for field `{0}`")]
+    UnknownConversion(String),
+    #[error("failed to parse `{value}` as {expected}")]
+    Parse { value: String, expected: &'static str },
+}
+
+/// User-supplied overrides for the timestamp conversions `Conversion::from_schema`
+/// would otherwise default to, e.g. from a `--datetime-format` CLI flag.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionOverrides {
+    /// A fixed-UTC `strftime`-style format, used when the tap emits timestamps
+    /// without an offset.
+    pub datetime_format: Option<String>,
+    /// A timezone-aware `strftime`-style format, used when the tap embeds an offset
+    /// or zone abbreviation in the timestamp string.
+    pub datetime_format_tz: Option<String>,
+}
+
+/// A per-field value coercion, derived from a Singer schema property's `type`/`format`
+/// plus any [`ConversionOverrides`]. Singer records are loosely-typed JSON (numbers and
+/// booleans often arrive as strings, timestamps in whatever format the tap chose), so
+/// this normalizes each field's JSON representation before it reaches Arrow.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    /// Pass the value through unchanged (strings with no further structure).
+    Bytes,
+    Integer,
+    Float,
+    Boolean,
+    /// RFC3339, the Singer spec's default.
+    Timestamp,
+    /// A fixed-UTC `strftime`-style format.
+    TimestampFmt(String),
+    /// A timezone-aware `strftime`-style format.
+    TimestampTzFmt(String),
+}
+
+impl Conversion {
+    /// Derive the conversion for a schema property, preferring a format override
+    /// over the RFC3339 default for `format: date-time` strings.
+    pub fn from_schema(prop: &Value, overrides: &ConversionOverrides) -> Result<Self, ConversionError> {
+        let type_str = prop
+            .get("type")
+            .and_then(Value::as_str)
+            .or_else(|| {
+                prop.get("type")?
+                    .as_array()?
+                    .iter()
+                    .find_map(|t| t.as_str().filter(|t| *t != "null"))
+            })
+            .unwrap_or("string");
+
+        Ok(match type_str {
+            "integer" => Conversion::Integer,
+            "number" => Conversion::Float,
+            "boolean" => Conversion::Boolean,
+            "string" => match prop.get("format").and_then(Value::as_str) {
+                Some("date-time") => match (&overrides.datetime_format_tz, &overrides.datetime_format) {
+                    (Some(fmt), _) => Conversion::TimestampTzFmt(fmt.clone()),
+                    (None, Some(fmt)) => Conversion::TimestampFmt(fmt.clone()),
+                    (None, None) => Conversion::Timestamp,
+                },
+                _ => Conversion::Bytes,
+            },
+            _ => Conversion::Bytes,
+        })
+    }
+
+    /// Coerce a raw JSON value into the representation its target Arrow column
+    /// expects, parsing numeric/boolean/timestamp strings as needed.
+    pub fn convert(&self, value: &Value) -> Result<Value, ConversionError> {
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+
+        match self {
+            Conversion::Bytes => Ok(value.clone()),
+            Conversion::Integer => match value {
+                Value::Number(_) => Ok(value.clone()),
+                Value::String(s) => s
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .map_err(|_| Conversion::parse_error(s, "integer")),
+                _ => Err(Conversion::parse_error(&value.to_string(), "integer")),
+            },
+            Conversion::Float => match value {
+                Value::Number(_) => Ok(value.clone()),
+                Value::String(s) => s
+                    .parse::<f64>()
+                    .map(Value::from)
+                    .map_err(|_| Conversion::parse_error(s, "number")),
+                _ => Err(Conversion::parse_error(&value.to_string(), "number")),
+            },
+            Conversion::Boolean => match value {
+                Value::Bool(_) => Ok(value.clone()),
+                Value::String(s) => match s.as_str() {
+                    "true" | "1" => Ok(Value::Bool(true)),
+                    "false" | "0" => Ok(Value::Bool(false)),
+                    _ => Err(Conversion::parse_error(s, "boolean")),
+                },
+                _ => Err(Conversion::parse_error(&value.to_string(), "boolean")),
+            },
+            Conversion::Timestamp => {
+                let s = value
+                    .as_str()
+                    .ok_or_else(|| Conversion::parse_error(&value.to_string(), "RFC3339 timestamp"))?;
+                DateTime::parse_from_rfc3339(s)
+                    .map(|dt| Value::String(dt.with_timezone(&Utc).to_rfc3339()))
+                    .map_err(|_| Conversion::parse_error(s, "RFC3339 timestamp"))
+            }
+            Conversion::TimestampFmt(fmt) => {
+                let s = value
+                    .as_str()
+                    .ok_or_else(|| Conversion::parse_error(&value.to_string(), "timestamp"))?;
+                NaiveDateTime::parse_from_str(s, fmt)
+                    .map(|dt| Value::String(dt.and_utc().to_rfc3339()))
+                    .map_err(|_| Conversion::parse_error(s, "timestamp"))
+            }
+            Conversion::TimestampTzFmt(fmt) => {
+                let s = value
+                    .as_str()
+                    .ok_or_else(|| Conversion::parse_error(&value.to_string(), "timestamp with timezone"))?;
+                DateTime::parse_from_str(s, fmt)
+                    .map(|dt| Value::String(dt.with_timezone(&Utc).to_rfc3339()))
+                    .map_err(|_| Conversion::parse_error(s, "timestamp with timezone"))
+            }
+        }
+    }
+
+    fn parse_error(value: &str, expected: &'static str) -> ConversionError {
+        ConversionError::Parse {
+            value: value.to_string(),
+            expected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_schema_defaults() {
+        let overrides = ConversionOverrides::default();
+        assert_eq!(
+            Conversion::from_schema(&json!({"type": "integer"}), &overrides).unwrap(),
+            Conversion::Integer
+        );
+        assert_eq!(
+            Conversion::from_schema(&json!({"type": "string", "format": "date-time"}), &overrides)
+                .unwrap(),
+            Conversion::Timestamp
+        );
+        assert_eq!(
+            Conversion::from_schema(&json!({"type": "string"}), &overrides).unwrap(),
+            Conversion::Bytes
+        );
+    }
+
+    #[test]
+    fn test_from_schema_respects_format_override() {
+        let overrides = ConversionOverrides {
+            datetime_format: Some("%Y-%m-%d %H:%M:%S".to_string()),
+            datetime_format_tz: None,
+        };
+        assert_eq!(
+            Conversion::from_schema(&json!({"type": "string", "format": "date-time"}), &overrides)
+                .unwrap(),
+            Conversion::TimestampFmt("%Y-%m-%d %H:%M:%S".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_numeric_strings() {
+        assert_eq!(
+            Conversion::Integer.convert(&json!("42")).unwrap(),
+            json!(42)
+        );
+        assert_eq!(
+            Conversion::Float.convert(&json!("4.2")).unwrap(),
+            json!(4.2)
+        );
+        assert_eq!(
+            Conversion::Boolean.convert(&json!("true")).unwrap(),
+            json!(true)
+        );
+    }
+
+    #[test]
+    fn test_convert_timestamp_fmt() {
+        let conversion = Conversion::TimestampFmt("%Y-%m-%d %H:%M:%S".to_string());
+        let converted = conversion.convert(&json!("2023-04-10 00:00:10")).unwrap();
+        assert_eq!(converted, json!("2023-04-10T00:00:10+00:00"));
+    }
+
+    #[test]
+    fn test_convert_null_passes_through() {
+        assert_eq!(Conversion::Integer.convert(&Value::Null).unwrap(), Value::Null);
+    }
+}
@@ -1,10 +1,8 @@
-use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::array::ArrayRef;
-use arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+use arrow::datatypes::{DataType, Field, Fields, Schema as ArrowSchema, TimeUnit};
 use arrow::record_batch::RecordBatch;
-use arrow_array::builder::StringBuilder;
+use arrow_json::ReaderBuilder;
 use serde_json::Value;
 use singer_rust::message::Message;
 use thiserror::Error;
@@ -21,20 +19,89 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("Parquet error: {0}")]
     Parquet(#[from] parquet::errors::ParquetError),
+    #[error("Conversion error: {0}")]
+    Conversion(#[from] conversion::ConversionError),
+    #[error("Batch error: {0}")]
+    Batch(#[from] singer_rust::batch::BatchError),
 }
 
-/// Convert JSON schema types to Arrow data types
-pub fn json_type_to_arrow(type_obj: &Value) -> Result<DataType, Error> {
-    let type_str = type_obj
-        .as_str()
-        .ok_or_else(|| Error::TypeConversion("Type must be a string".to_string()))?;
+/// Convert a JSON-Schema `type` keyword (a single type string, or a `["null", "..."]`
+/// union as taps emit for nullable columns) to an Arrow data type.
+///
+/// Returns the data type together with whether the union form marked the column
+/// nullable, so callers don't have to re-inspect `type_obj`.
+fn json_type_to_arrow(type_obj: &Value, prop: &Value) -> Result<(DataType, bool), Error> {
+    match type_obj {
+        Value::String(type_str) => Ok((scalar_type_to_arrow(type_str, prop)?, false)),
+        Value::Array(types) => {
+            let mut nullable = false;
+            let mut data_type = None;
+            for t in types {
+                let t = t
+                    .as_str()
+                    .ok_or_else(|| Error::TypeConversion("Type must be a string".to_string()))?;
+                if t == "null" {
+                    nullable = true;
+                } else {
+                    data_type = Some(scalar_type_to_arrow(t, prop)?);
+                }
+            }
+            let data_type = data_type.ok_or_else(|| {
+                Error::TypeConversion("Union type has no non-null branch".to_string())
+            })?;
+            Ok((data_type, nullable))
+        }
+        _ => Err(Error::TypeConversion(
+            "Type must be a string or an array of strings".to_string(),
+        )),
+    }
+}
 
+/// Convert a single (non-`null`) JSON-Schema type keyword, honoring `format` and
+/// decimal annotations, to an Arrow data type.
+fn scalar_type_to_arrow(type_str: &str, prop: &Value) -> Result<DataType, Error> {
     match type_str {
-        "string" => Ok(DataType::Utf8),
+        "string" => match prop.get("format").and_then(Value::as_str) {
+            Some("date-time") => Ok(DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))),
+            Some("date") => Ok(DataType::Date32),
+            Some("time") => Ok(DataType::Time64(TimeUnit::Microsecond)),
+            _ => Ok(DataType::Utf8),
+        },
         "integer" => Ok(DataType::Int64),
-        "number" => Ok(DataType::Float64),
+        "number" => {
+            let sql_type = prop.get("x-sql-datatype").and_then(Value::as_str);
+            let multiple_of_scale = prop.get("multipleOf").and_then(multiple_of_to_scale);
+            let is_decimal =
+                multiple_of_scale.is_some() || sql_type.is_some_and(is_decimal_sql_type);
+            if !is_decimal {
+                return Ok(DataType::Float64);
+            }
+
+            let sql_spec = sql_type.and_then(decimal_spec_from_sql_type);
+            let precision = sql_spec.map(|(precision, _)| precision).unwrap_or(38);
+            // `multipleOf` is the more explicit hint when both are present; otherwise
+            // fall back to the scale carried in e.g. `NUMERIC(38, 9)`.
+            let scale = multiple_of_scale
+                .or_else(|| sql_spec.map(|(_, scale)| scale))
+                .unwrap_or(0);
+            Ok(DataType::Decimal128(precision, scale))
+        }
         "boolean" => Ok(DataType::Boolean),
-        // Handle more types and formats
+        "object" => json_schema_to_struct(prop),
+        "array" => {
+            let items = prop
+                .get("items")
+                .ok_or_else(|| Error::Schema("Array property missing items".to_string()))?;
+            let (item_type, item_nullable) = json_type_to_arrow(
+                items.get("type").unwrap_or(&Value::String("string".into())),
+                items,
+            )?;
+            Ok(DataType::List(Arc::new(Field::new(
+                "item",
+                item_type,
+                item_nullable,
+            ))))
+        }
         _ => Err(Error::TypeConversion(format!(
             "Unsupported type: {}",
             type_str
@@ -42,29 +109,81 @@ pub fn json_type_to_arrow(type_obj: &Value) -> Result<DataType, Error> {
     }
 }
 
-/// Convert Singer schema to Arrow schema
+/// `multipleOf: 0.01` etc. is the closest JSON-Schema has to a decimal scale hint;
+/// count the fractional digits to recover it.
+fn multiple_of_to_scale(multiple_of: &Value) -> Option<i8> {
+    let s = multiple_of.as_f64()?;
+    let text = format!("{}", s);
+    let scale = text.split('.').nth(1).map(|frac| frac.len()).unwrap_or(0);
+    Some(scale as i8)
+}
+
+/// Whether an `x-sql-datatype` hint names a warehouse decimal/numeric type, e.g.
+/// `NUMERIC(38, 9)` or `DECIMAL(10,2)`, as opposed to e.g. `FLOAT8` or `INT`.
+fn is_decimal_sql_type(sql_type: &str) -> bool {
+    let upper = sql_type.trim_start().to_ascii_uppercase();
+    upper.starts_with("NUMERIC") || upper.starts_with("DECIMAL")
+}
+
+/// `x-sql-datatype: "NUMERIC(38, 9)"`-style hints carry the precision and scale
+/// Singer taps borrow from the warehouse-specific numeric type.
+fn decimal_spec_from_sql_type(sql_type: &str) -> Option<(u8, i8)> {
+    let inner = sql_type.split('(').nth(1)?.trim_end_matches(')');
+    let mut parts = inner.split(',');
+    let precision = parts.next()?.trim().parse().ok()?;
+    let scale = parts
+        .next()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0);
+    Some((precision, scale))
+}
+
+/// Recurse into a nested `"type": "object"` schema and build an Arrow `Struct`.
+fn json_schema_to_struct(prop: &Value) -> Result<DataType, Error> {
+    let properties = prop
+        .get("properties")
+        .ok_or_else(|| Error::Schema("Object property missing properties".to_string()))?;
+    let required = prop
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|req| {
+            req.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let fields: Result<Vec<Field>, Error> = properties
+        .as_object()
+        .ok_or_else(|| Error::Schema("Properties must be an object".to_string()))?
+        .iter()
+        .map(|(name, child)| property_to_field(name, child, &required))
+        .collect();
+
+    Ok(DataType::Struct(Fields::from(fields?)))
+}
+
+/// Build an Arrow `Field` for a single schema property, deriving nullability from
+/// either the `["null", ...]` union form or presence in the parent's `required` array.
+fn property_to_field(name: &str, prop: &Value, required: &[String]) -> Result<Field, Error> {
+    let type_value = prop
+        .get("type")
+        .ok_or_else(|| Error::Schema(format!("Property {} missing type", name)))?;
+    let (data_type, union_nullable) = json_type_to_arrow(type_value, prop)?;
+    let nullable = union_nullable || !required.iter().any(|r| r == name);
+    Ok(Field::new(name, data_type, nullable))
+}
+
+/// Convert a Singer `SCHEMA` message to an Arrow schema, deriving each field's
+/// nullability from a `["null", ...]` type union when present, falling back to the
+/// schema's top-level `required` array otherwise.
 pub fn singer_schema_to_arrow(schema_msg: &Message) -> Result<ArrowSchema, Error> {
     match schema_msg {
-        Message::SCHEMA { schema, .. } => {
-            let properties = schema
-                .get("properties")
-                .ok_or_else(|| Error::Schema("Schema missing properties".to_string()))?;
-
-            let fields: Result<Vec<Field>, Error> = properties
-                .as_object()
-                .ok_or_else(|| Error::Schema("Properties must be an object".to_string()))?
-                .iter()
-                .map(|(name, prop)| {
-                    let type_value = prop
-                        .get("type")
-                        .ok_or_else(|| Error::Schema(format!("Property {} missing type", name)))?;
-                    let data_type = json_type_to_arrow(type_value)?;
-                    Ok(Field::new(name, data_type, false))
-                })
-                .collect();
-
-            Ok(ArrowSchema::new(fields?))
-        }
+        Message::SCHEMA { schema, .. } => match json_schema_to_struct(schema)? {
+            DataType::Struct(fields) => Ok(ArrowSchema::new(fields)),
+            _ => unreachable!("json_schema_to_struct always returns DataType::Struct"),
+        },
         _ => Err(Error::Schema("Not a SCHEMA message".to_string())),
     }
 }
@@ -80,39 +199,27 @@ impl ToRecordBatch for Vec<Message> {
             return Ok(RecordBatch::new_empty(Arc::new(schema.clone())));
         }
 
-        let mut builders: HashMap<String, StringBuilder> = schema
-            .fields()
-            .iter()
-            .map(|field| (field.name().clone(), StringBuilder::new()))
-            .collect();
+        // Feed each record's JSON straight into arrow-json's tape decoder instead of
+        // stringifying every cell through a StringBuilder: the decoder coerces numbers,
+        // booleans and nested values per the target field and produces typed arrays
+        // directly, so there's no per-value `to_string` allocation on the hot path.
+        let mut decoder = ReaderBuilder::new(Arc::new(schema.clone()))
+            .build_decoder()?;
 
-        // Convert records to columns
         for msg in self {
             if let Message::RECORD { record, .. } = msg {
-                for (name, builder) in builders.iter_mut() {
-                    let value = record.get(name);
-                    match value {
-                        Some(v) => builder.append_value(v.to_string()),
-                        None => builder.append_null(),
-                    }
-                }
+                decoder.serialize(std::slice::from_ref(record))?;
             }
         }
 
-        // Finalize arrays
-        let arrays: Result<Vec<ArrayRef>, Error> = schema
-            .fields()
-            .iter()
-            .map(|field| {
-                let mut builder = builders.remove(field.name()).unwrap();
-                Ok(Arc::new(builder.finish()) as ArrayRef)
-            })
-            .collect();
-
-        Ok(RecordBatch::try_new(Arc::new(schema.clone()), arrays?)?)
+        decoder
+            .flush()?
+            .ok_or_else(|| Error::Schema("Decoder produced no record batch".to_string()))
     }
 }
 
+pub mod cli;
+pub mod conversion;
 pub mod target;
 pub use target::ParquetTarget;
 
@@ -122,21 +229,90 @@ mod tests {
     use serde_json::json;
 
     #[test]
-    fn test_json_type_to_arrow() {
+    fn test_scalar_type_to_arrow() {
         assert_eq!(
-            json_type_to_arrow(&json!("string")).unwrap(),
+            scalar_type_to_arrow("string", &json!({})).unwrap(),
             DataType::Utf8
         );
         assert_eq!(
-            json_type_to_arrow(&json!("integer")).unwrap(),
+            scalar_type_to_arrow("integer", &json!({})).unwrap(),
             DataType::Int64
         );
         assert_eq!(
-            json_type_to_arrow(&json!("number")).unwrap(),
+            scalar_type_to_arrow("number", &json!({})).unwrap(),
             DataType::Float64
         );
     }
 
+    #[test]
+    fn test_decimal_detected_from_multiple_of() {
+        assert_eq!(
+            scalar_type_to_arrow("number", &json!({"multipleOf": 0.01})).unwrap(),
+            DataType::Decimal128(38, 2)
+        );
+    }
+
+    #[test]
+    fn test_decimal_detected_from_sql_datatype_alone() {
+        assert_eq!(
+            scalar_type_to_arrow("number", &json!({"x-sql-datatype": "NUMERIC(38, 9)"})).unwrap(),
+            DataType::Decimal128(38, 9)
+        );
+    }
+
+    #[test]
+    fn test_multiple_of_scale_wins_over_sql_datatype_scale() {
+        assert_eq!(
+            scalar_type_to_arrow(
+                "number",
+                &json!({"multipleOf": 0.01, "x-sql-datatype": "NUMERIC(38, 9)"})
+            )
+            .unwrap(),
+            DataType::Decimal128(38, 2)
+        );
+    }
+
+    #[test]
+    fn test_non_decimal_sql_datatype_falls_back_to_float() {
+        assert_eq!(
+            scalar_type_to_arrow("number", &json!({"x-sql-datatype": "FLOAT8"})).unwrap(),
+            DataType::Float64
+        );
+    }
+
+    #[test]
+    fn test_nullable_union_type() {
+        let field = property_to_field(
+            "name",
+            &json!({"type": ["null", "string"]}),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(field.data_type(), &DataType::Utf8);
+        assert!(field.is_nullable());
+    }
+
+    #[test]
+    fn test_required_marks_non_nullable() {
+        let field = property_to_field("id", &json!({"type": "integer"}), &["id".to_string()])
+            .unwrap();
+        assert!(!field.is_nullable());
+    }
+
+    #[test]
+    fn test_date_time_format_maps_to_timestamp() {
+        let field = property_to_field(
+            "created_at",
+            &json!({"type": "string", "format": "date-time"}),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            field.data_type(),
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+        );
+    }
+
     #[test]
     fn test_record_to_batch() {
         let schema = ArrowSchema::new(vec![
@@ -1,77 +1,385 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, Read};
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use arrow::datatypes::Schema as ArrowSchema;
-use arrow::record_batch::RecordBatch;
 use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
+use serde_json::Value;
+use singer_rust::log::{JsonLogEmitter, LogEmitter};
 use singer_rust::message::Message;
 
+use crate::conversion::{Conversion, ConversionOverrides};
 use crate::{singer_schema_to_arrow, Error, ToRecordBatch};
 
-pub struct ParquetTarget {
+/// Derive a per-field [`Conversion`] for every property in a stream's JSON schema.
+fn field_conversions(
+    schema: &Value,
+    overrides: &ConversionOverrides,
+) -> Result<HashMap<String, Conversion>, Error> {
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .ok_or_else(|| Error::Schema("Schema missing properties".to_string()))?;
+
+    properties
+        .iter()
+        .map(|(name, prop)| Ok((name.clone(), Conversion::from_schema(prop, overrides)?)))
+        .collect()
+}
+
+/// Per-stream Parquet writing state: a long-lived `ArrowWriter` over the stream's
+/// own output file, plus the records accumulated since the last row-group flush.
+struct StreamWriter {
+    stream: String,
     schema: ArrowSchema,
-    writer_properties: WriterProperties,
-    output_path: PathBuf,
-    batch_size: usize,
+    writer: ArrowWriter<File>,
     current_batch: Vec<Message>,
+    batch_size: usize,
+    conversions: HashMap<String, Conversion>,
+    row_groups_written: u32,
 }
 
-impl ParquetTarget {
-    pub fn new(
-        schema_message: &Message,
-        output_path: PathBuf,
+impl StreamWriter {
+    fn new(
+        stream: &str,
+        output_path: &PathBuf,
+        schema: ArrowSchema,
+        properties: WriterProperties,
         batch_size: usize,
+        conversions: HashMap<String, Conversion>,
     ) -> Result<Self, Error> {
-        let schema = singer_schema_to_arrow(schema_message)?;
-        let props = WriterProperties::builder()
-            .set_compression(Compression::SNAPPY)
-            .build();
-
+        let file = File::create(output_path)?;
+        let writer = ArrowWriter::try_new(file, Arc::new(schema.clone()), Some(properties))?;
         Ok(Self {
+            stream: stream.to_string(),
             schema,
-            writer_properties: props,
-            output_path,
-            batch_size,
+            writer,
             current_batch: Vec::with_capacity(batch_size),
+            batch_size,
+            conversions,
+            row_groups_written: 0,
         })
     }
 
-    pub fn add_record(&mut self, record: Message) -> Result<(), Error> {
-        if let Message::RECORD { .. } = record {
-            self.current_batch.push(record);
+    /// Coerce a record's field values (numeric/boolean/timestamp strings) per the
+    /// stream's schema before it's buffered for the next batch.
+    fn convert_record(&self, record: Message) -> Result<Message, Error> {
+        let Message::RECORD {
+            stream,
+            record,
+            version,
+            time_extracted,
+        } = record
+        else {
+            return Ok(record);
+        };
 
-            if self.current_batch.len() >= self.batch_size {
-                self.flush()?;
+        let record = match record {
+            Value::Object(fields) => {
+                let converted: Result<serde_json::Map<String, Value>, Error> = fields
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = match self.conversions.get(&name) {
+                            Some(conversion) => conversion.convert(&value)?,
+                            None => value,
+                        };
+                        Ok((name, value))
+                    })
+                    .collect();
+                Value::Object(converted?)
             }
-        }
+            other => other,
+        };
 
-        Ok(())
+        Ok(Message::RECORD {
+            stream,
+            record,
+            version,
+            time_extracted,
+        })
     }
 
-    pub fn flush(&mut self) -> Result<(), Error> {
+    fn add_record(&mut self, record: Message) -> Result<bool, Error> {
+        let record = self.convert_record(record)?;
+        self.current_batch.push(record);
+        Ok(self.current_batch.len() >= self.batch_size)
+    }
+
+    /// Write the accumulated records as a new row group, returning the row-group
+    /// count so far. The underlying `ArrowWriter` stays open across calls, so
+    /// repeated flushes append row groups instead of truncating the file the way a
+    /// per-flush `File::create` would.
+    fn flush(&mut self) -> Result<Option<u32>, Error> {
         if self.current_batch.is_empty() {
-            return Ok(());
+            return Ok(None);
         }
 
         let batch = self.current_batch.to_record_batch(&self.schema)?;
-        self.write_batch(&batch)?;
+        self.writer.write(&batch)?;
+        self.writer.flush()?;
         self.current_batch.clear();
+        self.row_groups_written += 1;
+        Ok(Some(self.row_groups_written))
+    }
 
+    fn close(mut self) -> Result<(), Error> {
+        self.flush()?;
+        self.writer.close()?;
         Ok(())
     }
+}
+
+/// A Singer target that writes each stream to its own Parquet file under `base_dir`,
+/// keeping a [`StreamWriter`] open per stream for the lifetime of the sync so that
+/// every `flush()` appends a row group rather than overwriting the whole file.
+pub struct ParquetTarget {
+    base_dir: PathBuf,
+    batch_size: usize,
+    writer_properties: WriterProperties,
+    conversion_overrides: ConversionOverrides,
+    logger: Option<JsonLogEmitter>,
+    writers: HashMap<String, StreamWriter>,
+    /// How many times each stream's schema has changed, so a schema change rolls
+    /// to a new output file instead of truncating the rows already written under
+    /// the old schema.
+    schema_generations: HashMap<String, u32>,
+}
+
+impl ParquetTarget {
+    pub fn new(base_dir: PathBuf, batch_size: usize) -> Self {
+        Self {
+            base_dir,
+            batch_size,
+            writer_properties: WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build(),
+            conversion_overrides: ConversionOverrides::default(),
+            logger: None,
+            writers: HashMap::new(),
+            schema_generations: HashMap::new(),
+        }
+    }
+
+    /// Override the timestamp formats used to coerce `format: date-time` fields,
+    /// e.g. from a `--datetime-format` CLI flag.
+    pub fn with_conversion_overrides(mut self, overrides: ConversionOverrides) -> Self {
+        self.conversion_overrides = overrides;
+        self
+    }
+
+    /// Emit a JSON log record to stderr every time a stream's writer flushes a row
+    /// group, reusing the same [`LogEmitter`] `singer-summarize` logs messages through.
+    pub fn with_json_logging(mut self) -> Self {
+        self.logger = Some(JsonLogEmitter);
+        self
+    }
+
+    fn log_flush(&self, stream: &str, row_groups_written: u32) {
+        if let Some(logger) = &self.logger {
+            logger.emit(
+                "info",
+                &[
+                    ("stream", serde_json::json!(stream)),
+                    ("msg_type", serde_json::json!("ROW_GROUP_FLUSH")),
+                    ("running_count", serde_json::json!(row_groups_written)),
+                ],
+            );
+        }
+    }
+
+    /// The output path for a stream's `generation`-th schema, e.g. `orders.parquet`
+    /// for the first schema seen and `orders.2.parquet` for the next one, so a
+    /// mid-stream schema change never truncates the file the previous schema's
+    /// rows were already flushed to.
+    fn output_path(&self, stream: &str, generation: u32) -> PathBuf {
+        if generation == 0 {
+            self.base_dir.join(format!("{}.parquet", stream))
+        } else {
+            self.base_dir
+                .join(format!("{}.{}.parquet", stream, generation + 1))
+        }
+    }
+
+    /// Handle a `SCHEMA` message: open the stream's writer on first sight, or roll
+    /// over to a new, separately numbered output file (closing and finalizing the
+    /// previous one) if the schema changed. The previous file's row groups are
+    /// never touched, so a schema change mid-sync can't discard rows already
+    /// written under the old schema.
+    pub fn add_schema(&mut self, schema_message: &Message) -> Result<(), Error> {
+        let (stream, schema_value) = match schema_message {
+            Message::SCHEMA { stream, schema, .. } => (stream, schema),
+            _ => return Err(Error::Schema("Expected SCHEMA message".to_string())),
+        };
+        let schema = singer_schema_to_arrow(schema_message)?;
+
+        if let Some(existing) = self.writers.get(stream) {
+            if existing.schema == schema {
+                return Ok(());
+            }
+        }
+        let generation = self.schema_generations.entry(stream.clone()).or_insert(0);
+        if let Some(previous) = self.writers.remove(stream) {
+            previous.close()?;
+            *generation += 1;
+        }
+        let generation = *generation;
 
-    fn write_batch(&self, batch: &RecordBatch) -> Result<(), Error> {
-        let file = std::fs::File::create(&self.output_path)?;
-        let mut writer = ArrowWriter::try_new(
-            file,
-            Arc::new(self.schema.clone()),
-            Some(self.writer_properties.clone()),
+        let conversions = field_conversions(schema_value, &self.conversion_overrides)?;
+        let output_path = self.output_path(stream, generation);
+        let writer = StreamWriter::new(
+            stream,
+            &output_path,
+            schema,
+            self.writer_properties.clone(),
+            self.batch_size,
+            conversions,
         )?;
+        self.writers.insert(stream.clone(), writer);
+        Ok(())
+    }
+
+    /// Dispatch a `RECORD` message to its stream's writer.
+    pub fn add_record(&mut self, record: Message) -> Result<(), Error> {
+        let stream = match &record {
+            Message::RECORD { stream, .. } => stream.clone(),
+            _ => return Ok(()),
+        };
+        let writer = self.writers.get_mut(&stream).ok_or_else(|| {
+            Error::Schema(format!("Received RECORD for {} before its SCHEMA", stream))
+        })?;
+        let should_flush = writer.add_record(record)?;
+        if should_flush {
+            self.flush(&stream)?;
+        }
+        Ok(())
+    }
+
+    /// Resolve and decode a `BATCH` message's manifest, folding its records into the
+    /// same per-stream pipeline as inline `RECORD` messages so fast-path batch files
+    /// end up in the stream's Parquet output too.
+    pub fn add_batch(&mut self, batch_message: &Message) -> Result<(), Error> {
+        let Message::BATCH {
+            stream,
+            manifest,
+            encoding,
+        } = batch_message
+        else {
+            return Err(Error::Schema("Expected BATCH message".to_string()));
+        };
+
+        let records = singer_rust::batch::read_batch(manifest, encoding)?;
+        for record in records {
+            self.add_record(Message::RECORD {
+                stream: stream.clone(),
+                record,
+                version: 0,
+                time_extracted: None,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Flush a single stream's accumulated batch as a new row group.
+    pub fn flush(&mut self, stream: &str) -> Result<(), Error> {
+        let Some(writer) = self.writers.get_mut(stream) else {
+            return Ok(());
+        };
+        if let Some(row_groups_written) = writer.flush()? {
+            self.log_flush(stream, row_groups_written);
+        }
+        Ok(())
+    }
 
-        writer.write(batch)?;
-        writer.close()?;
+    /// Flush every stream's accumulated batch.
+    pub fn flush_all(&mut self) -> Result<(), Error> {
+        let streams: Vec<String> = self.writers.keys().cloned().collect();
+        for stream in streams {
+            self.flush(&stream)?;
+        }
+        Ok(())
+    }
+
+    /// Flush and close every stream's writer, finalizing the Parquet footers.
+    pub fn finish(&mut self) -> Result<(), Error> {
+        for (_, writer) in self.writers.drain() {
+            writer.close()?;
+        }
+        Ok(())
+    }
+
+    /// Consume a full, possibly multi-stream Singer message stream and finalize all
+    /// writers once `reader` reaches EOF. This is the entry point a `singer-arrow`
+    /// binary would drive from stdin.
+    pub fn run(&mut self, reader: impl Read) -> Result<(), Error> {
+        let buffer = std::io::BufReader::new(reader);
+        for line in buffer.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message = Message::from_string(&line)
+                .map_err(|e| Error::Schema(format!("Invalid Singer message: {}", e)))?;
+            match &message {
+                Message::SCHEMA { .. } => self.add_schema(&message)?,
+                Message::RECORD { .. } => self.add_record(message)?,
+                Message::BATCH { .. } => self.add_batch(&message)?,
+                // STATE and ACTIVATE_VERSION don't affect the per-stream writers
+                // directly; a richer target would track bookmarks here.
+                _ => {}
+            }
+        }
+        self.finish()
+    }
+}
+
+/// Async counterpart of [`ParquetTarget`]'s per-stream flush, for targets embedded in
+/// a Tokio runtime. Encoding and row-group writes are CPU-bound, so this hands the
+/// work to `spawn_blocking` on the blocking thread pool rather than blocking the
+/// async caller's executor thread outright, letting a target await network/object-store
+/// work between batches. Unlike `tokio::task::block_in_place`, `spawn_blocking` doesn't
+/// require a multi-threaded runtime, so it won't panic under a single-threaded
+/// `#[tokio::main(flavor = "current_thread")]` embedding.
+#[cfg(feature = "async")]
+impl ParquetTarget {
+    pub async fn add_record_async(&mut self, record: Message) -> Result<(), Error> {
+        let stream = match &record {
+            Message::RECORD { stream, .. } => stream.clone(),
+            _ => return Ok(()),
+        };
+        let should_flush = {
+            let writer = self.writers.get_mut(&stream).ok_or_else(|| {
+                Error::Schema(format!("Received RECORD for {} before its SCHEMA", stream))
+            })?;
+            writer.add_record(record)?
+        };
+        if should_flush {
+            self.flush_async(&stream).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn flush_async(&mut self, stream: &str) -> Result<(), Error> {
+        let Some(mut writer) = self.writers.remove(stream) else {
+            return Ok(());
+        };
+
+        // `ArrowWriter` isn't `'static`-friendly to borrow into a blocking task, so
+        // the writer is moved in by value and handed back alongside the result.
+        let (result, writer) = tokio::task::spawn_blocking(move || {
+            let result = writer.flush();
+            (result, writer)
+        })
+        .await
+        .map_err(|err| Error::Schema(format!("flush task panicked: {}", err)))?;
+
+        self.writers.insert(stream.to_string(), writer);
+        if let Some(row_groups_written) = result? {
+            self.log_flush(stream, row_groups_written);
+        }
         Ok(())
     }
 }
@@ -82,59 +390,121 @@ mod tests {
     use serde_json::json;
     use tempfile::tempdir;
 
+    fn schema_message(stream: &str) -> Message {
+        Message::SCHEMA {
+            stream: stream.to_string(),
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" }
+                }
+            }),
+            key_properties: vec!["id".to_string()],
+            bookmark_properties: vec![],
+        }
+    }
+
+    fn record(stream: &str, id: &str, name: &str) -> Message {
+        Message::RECORD {
+            stream: stream.to_string(),
+            record: json!({ "id": id, "name": name }),
+            version: 1,
+            time_extracted: None,
+        }
+    }
+
+    #[test]
+    fn test_parquet_target_single_stream() {
+        let temp_dir = tempdir().unwrap();
+        let mut target = ParquetTarget::new(temp_dir.path().to_path_buf(), 2);
+
+        target.add_schema(&schema_message("test")).unwrap();
+        target.add_record(record("test", "1", "Alice")).unwrap();
+        target.add_record(record("test", "2", "Bob")).unwrap();
+        target.flush("test").unwrap();
+        target.finish().unwrap();
+
+        let output_path = temp_dir.path().join("test.parquet");
+        assert!(output_path.exists());
+        assert!(output_path.metadata().unwrap().len() > 0);
+    }
+
+    #[test]
+    fn test_parquet_target_interleaved_streams() {
+        let temp_dir = tempdir().unwrap();
+        let mut target = ParquetTarget::new(temp_dir.path().to_path_buf(), 10);
+
+        target.add_schema(&schema_message("a")).unwrap();
+        target.add_schema(&schema_message("b")).unwrap();
+        target.add_record(record("a", "1", "Alice")).unwrap();
+        target.add_record(record("b", "1", "Bob")).unwrap();
+        target.add_record(record("a", "2", "Carol")).unwrap();
+        target.flush_all().unwrap();
+        target.finish().unwrap();
+
+        assert!(temp_dir.path().join("a.parquet").exists());
+        assert!(temp_dir.path().join("b.parquet").exists());
+    }
+
     #[test]
-    fn test_parquet_target() {
+    fn test_record_before_schema_is_an_error() {
         let temp_dir = tempdir().unwrap();
+        let mut target = ParquetTarget::new(temp_dir.path().to_path_buf(), 10);
+
+        assert!(target.add_record(record("test", "1", "Alice")).is_err());
+    }
+
+    #[test]
+    fn test_multiple_flushes_append_row_groups() {
+        let temp_dir = tempdir().unwrap();
+        let mut target = ParquetTarget::new(temp_dir.path().to_path_buf(), 1);
+
+        target.add_schema(&schema_message("test")).unwrap();
+        target.add_record(record("test", "1", "Alice")).unwrap();
+        target.add_record(record("test", "2", "Bob")).unwrap();
+        target.finish().unwrap();
+
         let output_path = temp_dir.path().join("test.parquet");
+        let file = File::open(output_path).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        use parquet::file::reader::FileReader;
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+    }
 
-        let schema_message = Message::SCHEMA {
+    #[test]
+    fn test_schema_change_rolls_to_a_new_file_instead_of_truncating() {
+        let temp_dir = tempdir().unwrap();
+        let mut target = ParquetTarget::new(temp_dir.path().to_path_buf(), 10);
+
+        target.add_schema(&schema_message("test")).unwrap();
+        target.add_record(record("test", "1", "Alice")).unwrap();
+        target.flush("test").unwrap();
+
+        let changed_schema = Message::SCHEMA {
             stream: "test".to_string(),
             schema: json!({
                 "type": "object",
                 "properties": {
-                    "id": {
-                        "type": "string"
-                    },
-                    "name": {
-                        "type": "string"
-                    }
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "age": { "type": "integer" }
                 }
             }),
             key_properties: vec!["id".to_string()],
             bookmark_properties: vec![],
         };
+        target.add_schema(&changed_schema).unwrap();
+        target.finish().unwrap();
 
-        let mut target = ParquetTarget::new(&schema_message, output_path.clone(), 2).unwrap();
-
-        // Add some records
-        target
-            .add_record(Message::RECORD {
-                stream: "test".to_string(),
-                record: json!({
-                    "id": "1",
-                    "name": "Alice"
-                }),
-                version: 1,
-                time_extracted: None,
-            })
-            .unwrap();
-
-        target
-            .add_record(Message::RECORD {
-                stream: "test".to_string(),
-                record: json!({
-                    "id": "2",
-                    "name": "Bob"
-                }),
-                version: 1,
-                time_extracted: None,
-            })
-            .unwrap();
-
-        target.flush().unwrap();
+        let first_path = temp_dir.path().join("test.parquet");
+        let second_path = temp_dir.path().join("test.2.parquet");
+        assert!(first_path.exists());
+        assert!(second_path.exists());
 
-        // Verify file exists and has content
-        assert!(output_path.exists());
-        assert!(output_path.metadata().unwrap().len() > 0);
+        let file = File::open(first_path).unwrap();
+        let reader = parquet::file::reader::SerializedFileReader::new(file).unwrap();
+        use parquet::file::reader::FileReader;
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 1);
     }
 }
@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+/// Errors a [`MessageReader`](crate::message::MessageReader) or
+/// [`AsyncMessageReader`](crate::message::AsyncMessageReader) can encounter while
+/// reading and processing a stream of Singer messages.
+#[derive(Debug, Error)]
+pub enum SingerError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to deserialize Singer message: {0}")]
+    Deserialize(#[from] serde_json::Error),
+    #[error("unknown Singer message type: {0}")]
+    UnknownMessageType(String),
+    #[error("validation error: {0}")]
+    Validation(String),
+    #[error("batch error: {0}")]
+    Batch(#[from] crate::batch::BatchError),
+}
@@ -1,6 +1,13 @@
+pub use error::SingerError;
+pub use log::{JsonLogEmitter, LogEmitter};
 pub use message::{write_message, BatchEncoding, Message, MessageReader};
 
+pub mod batch;
+pub mod error;
+pub mod log;
 pub mod message;
+pub mod state;
+pub mod validation;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,35 @@
+use std::io::Write;
+
+use chrono::Utc;
+use serde_json::{Map, Value};
+
+/// A pluggable sink for structured, per-message progress logging. Implementors
+/// decide the wire format (line-delimited JSON, a key/value drain, ...) and where
+/// the line goes; callers just hand over a level and a bag of fields.
+///
+/// This lives in `singer_rust` (rather than `singer-summarize`) so other tools in the
+/// workspace, like the Parquet target, can log their own per-message events (e.g. a
+/// row-group flush) through the same sink.
+pub trait LogEmitter {
+    fn emit(&self, level: &str, fields: &[(&str, Value)]);
+}
+
+/// Emits one JSON object per log record, line-delimited, to stderr.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonLogEmitter;
+
+impl LogEmitter for JsonLogEmitter {
+    fn emit(&self, level: &str, fields: &[(&str, Value)]) {
+        let mut record = Map::with_capacity(fields.len() + 2);
+        record.insert("level".to_string(), Value::String(level.to_string()));
+        record.insert("ts".to_string(), Value::String(Utc::now().to_rfc3339()));
+        for (key, value) in fields {
+            record.insert((*key).to_string(), value.clone());
+        }
+
+        if let Ok(line) = serde_json::to_string(&Value::Object(record)) {
+            let mut stderr = std::io::stderr().lock();
+            let _ = writeln!(stderr, "{}", line);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use futures_core::Stream;
+use futures_util::stream::FuturesUnordered;
+use futures_util::StreamExt;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+use crate::error::SingerError;
+
+use super::{BatchEncoding, LineErrorPolicy, Message};
+
+/// An async counterpart to [`MessageReader`](super::MessageReader) for Tokio-based
+/// taps/targets that want to await IO (network calls, object-store writes, etc.)
+/// between Singer messages instead of blocking a thread per pipeline stage.
+///
+/// The per-message handlers take `&self` rather than `&mut self` so that
+/// [`process_lines_with_backpressure`](Self::process_lines_with_backpressure) can
+/// fan them out to run concurrently; implementations that need to mutate shared
+/// state (a stream's buffered batch, a counter, a network connection pool) should
+/// hold it behind interior mutability, e.g. a `tokio::sync::Mutex` per stream.
+#[async_trait::async_trait]
+pub trait AsyncMessageReader {
+    async fn process_line(&self, line: &str) -> Result<(), SingerError> {
+        let message: Message = serde_json::from_str(line)?;
+        self.dispatch(message).await
+    }
+
+    /// Route a decoded message to its handler. Shared by [`process_line`](Self::process_line)
+    /// and [`process_lines_with_backpressure`](Self::process_lines_with_backpressure), which
+    /// decode messages on a separate task from the one(s) that dispatch them.
+    async fn dispatch(&self, message: Message) -> Result<(), SingerError> {
+        match message {
+            Message::RECORD {
+                stream,
+                record,
+                version,
+                time_extracted,
+            } => {
+                self.process_record(stream, record, time_extracted, version)
+                    .await
+            }
+            Message::SCHEMA {
+                stream,
+                schema,
+                key_properties,
+                bookmark_properties,
+            } => {
+                self.process_schema(stream, schema, key_properties, bookmark_properties)
+                    .await
+            }
+            Message::STATE { value } => self.process_state(value).await,
+            Message::ACTIVATE_VERSION { stream, version } => {
+                self.process_activate_version(stream, version).await
+            }
+            Message::BATCH {
+                stream,
+                manifest,
+                encoding,
+            } => self.process_batch(stream, manifest, encoding).await,
+        }
+    }
+
+    /// Process a stream of Singer messages read from an `AsyncBufRead`, one line at a
+    /// time, awaiting each handler before reading the next line. Aborts on the first
+    /// line that fails to read, parse or process; see
+    /// [`process_lines_with_policy`](Self::process_lines_with_policy) to tolerate and
+    /// count bad lines instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader that implements `tokio::io::AsyncBufRead`.
+    async fn process_lines(
+        &self,
+        reader: impl AsyncBufRead + Unpin + Send,
+    ) -> Result<(), SingerError> {
+        self.process_lines_with_policy(reader, LineErrorPolicy::FailFast)
+            .await
+            .map(|_skipped| ())
+    }
+
+    /// Process a stream of Singer messages read from an `AsyncBufRead` under an
+    /// explicit [`LineErrorPolicy`], returning the number of lines dropped along the
+    /// way. IO errors reading a line are never policy-gated.
+    async fn process_lines_with_policy(
+        &self,
+        reader: impl AsyncBufRead + Unpin + Send,
+        policy: LineErrorPolicy,
+    ) -> Result<u64, SingerError> {
+        let mut skipped = 0;
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await? {
+            if let Err(err) = self.process_line(&line).await {
+                match policy {
+                    LineErrorPolicy::FailFast => return Err(err),
+                    LineErrorPolicy::SkipAndCount => skipped += 1,
+                }
+            }
+        }
+        Ok(skipped)
+    }
+
+    /// Process a stream of already-decoded lines, e.g. from `tokio_util::codec::LinesCodec`
+    /// wrapped around `tokio::io::stdin()`. Aborts on the first line that fails to
+    /// read, parse or process.
+    async fn process_stream(
+        &self,
+        mut stream: impl Stream<Item = std::io::Result<String>> + Unpin + Send,
+    ) -> Result<(), SingerError> {
+        while let Some(line) = stream.next().await {
+            self.process_line(&line?).await?;
+        }
+        Ok(())
+    }
+
+    /// Process a stream of Singer messages with the line decoder running on its own
+    /// task, connected by a channel bounded at `channel_capacity`, and every
+    /// non-`STATE` message's handler fanned out to run concurrently with the other
+    /// streams' in-flight handlers rather than one at a time. Once the channel
+    /// fills up, the decoder task blocks on `send` instead of reading further
+    /// ahead, so a slow handler still applies backpressure all the way back to the
+    /// input reader rather than letting an unbounded backlog build up in memory.
+    ///
+    /// A `STATE` message is an ordered barrier: before it is dispatched, every
+    /// handler fanned out so far — across every stream — is awaited to completion,
+    /// so a bookmark it carries never outruns the data it describes. Handlers are
+    /// otherwise free to complete in whatever order their network/object-store
+    /// work finishes.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader that implements `tokio::io::AsyncBufRead`.
+    /// * `channel_capacity` - The number of decoded messages the channel buffers
+    ///   before the decoder task blocks, and the most handlers kept in flight at once.
+    async fn process_lines_with_backpressure(
+        &self,
+        reader: impl AsyncBufRead + Unpin + Send + 'static,
+        channel_capacity: usize,
+    ) -> Result<(), SingerError>
+    where
+        Self: Sized + Sync,
+    {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Result<Message, SingerError>>(channel_capacity);
+
+        let decoder = tokio::spawn(async move {
+            let mut lines = reader.lines();
+            loop {
+                let next = lines.next_line().await;
+                let decoded = match next {
+                    Ok(Some(line)) => serde_json::from_str::<Message>(&line).map_err(SingerError::from),
+                    Ok(None) => break,
+                    Err(err) => Err(SingerError::from(err)),
+                };
+                let is_err = decoded.is_err();
+                if tx.send(decoded).await.is_err() || is_err {
+                    break;
+                }
+            }
+        });
+
+        let mut inflight: FuturesUnordered<Pin<Box<dyn Future<Output = Result<(), SingerError>> + '_>>> =
+            FuturesUnordered::new();
+        let mut decoder_done = false;
+
+        loop {
+            tokio::select! {
+                Some(result) = inflight.next(), if !inflight.is_empty() => {
+                    result?;
+                }
+                // Stop pulling from the channel while `channel_capacity` handlers are
+                // still in flight, so the channel fills and the decoder blocks.
+                decoded = rx.recv(), if !decoder_done && inflight.len() < channel_capacity => {
+                    match decoded {
+                        None => decoder_done = true,
+                        Some(decoded) => {
+                            let message = decoded?;
+                            if matches!(message, Message::STATE { .. }) {
+                                // Ordered barrier: drain every handler fanned out
+                                // so far before a STATE's bookmark can be trusted.
+                                while let Some(result) = inflight.next().await {
+                                    result?;
+                                }
+                                self.dispatch(message).await?;
+                            } else {
+                                inflight.push(Box::pin(self.dispatch(message)));
+                            }
+                        }
+                    }
+                }
+                else => break,
+            }
+        }
+
+        decoder.await.map_err(|err| {
+            SingerError::Validation(format!("line decoder task panicked: {}", err))
+        })?;
+        Ok(())
+    }
+
+    /// Process a single Singer `RECORD` message.
+    async fn process_record(
+        &self,
+        stream: String,
+        record: serde_json::Value,
+        time_extracted: Option<String>,
+        version: u64,
+    ) -> Result<(), SingerError>;
+
+    /// Process a single Singer `SCHEMA` message.
+    async fn process_schema(
+        &self,
+        stream: String,
+        schema: serde_json::Value,
+        key_properties: Vec<String>,
+        bookmark_properties: Vec<String>,
+    ) -> Result<(), SingerError>;
+
+    /// Process a single Singer `STATE` message.
+    async fn process_state(&self, value: serde_json::Value) -> Result<(), SingerError>;
+
+    /// Process a single Singer `ACTIVATE_VERSION` message.
+    async fn process_activate_version(
+        &self,
+        stream: String,
+        version: u64,
+    ) -> Result<(), SingerError>;
+
+    /// Process a single Singer `BATCH` message.
+    async fn process_batch(
+        &self,
+        stream: String,
+        manifest: Vec<String>,
+        encoding: BatchEncoding,
+    ) -> Result<(), SingerError>;
+}
@@ -1,11 +1,18 @@
 use std::io::{self, BufRead, BufReader, Read, Write};
 
-use serde_json;
+use serde_json::{self, Value};
+
+use crate::error::SingerError;
 
 use super::{BatchEncoding, Message};
 
 /// Write a Singer message to stdout.
 ///
+/// Numbers and object keys serialize through `serde_json`'s defaults, without its
+/// `arbitrary_precision`/`preserve_order` features; see
+/// [`Message::to_string`](super::Message::to_string) for the precision and key-order
+/// limits this carries.
+///
 /// # Arguments
 ///
 /// * `message` - A Singer message.
@@ -31,9 +38,25 @@ pub fn write_message(message: &Message) -> io::Result<()> {
     Ok(())
 }
 
+/// Controls what [`MessageReader::process_lines_with_policy`] does when an individual line
+/// fails to parse or be processed by a handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineErrorPolicy {
+    /// Abort the whole run on the first bad line. The default, since a dropped
+    /// line can mean a dropped record a target never gets another chance to see.
+    #[default]
+    FailFast,
+    /// Log-and-continue: skip the bad line and keep reading, tallying how many
+    /// were dropped so a long-running daemon can report it instead of dying.
+    SkipAndCount,
+}
+
 pub trait MessageReader {
-    fn process_line(&mut self, line: &str) -> Result<(), serde_json::Error> {
-        let message: Message = serde_json::from_str(line)?;
+    fn process_line(&mut self, line: &str) -> Result<(), SingerError> {
+        let message: Message = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(err) => return Err(classify_parse_error(line, err)),
+        };
         match message {
             Message::RECORD {
                 stream,
@@ -59,17 +82,39 @@ pub trait MessageReader {
         }
     }
 
-    /// Process a stream of Singer messages.
+    /// Process a stream of Singer messages, aborting the whole run on the first line
+    /// that fails to read, parse or process. For a policy that tolerates and counts
+    /// bad lines instead, see [`process_lines_with_policy`](Self::process_lines_with_policy).
     ///
     /// # Arguments
     ///
     /// * `reader` - A reader that implements `io::BufRead`.
-    fn process_lines(&mut self, buffer: BufReader<impl Read>) -> Result<(), serde_json::Error> {
+    fn process_lines(&mut self, buffer: BufReader<impl Read>) -> Result<(), SingerError> {
+        self.process_lines_with_policy(buffer, LineErrorPolicy::FailFast)
+            .map(|_skipped| ())
+    }
+
+    /// Process a stream of Singer messages under an explicit [`LineErrorPolicy`],
+    /// returning the number of lines dropped along the way. Under `FailFast` this is
+    /// always `0`, since any processing error there aborts the run instead of being
+    /// counted. IO errors reading a line are never policy-gated: the underlying
+    /// reader is assumed broken and the run aborts regardless of policy.
+    fn process_lines_with_policy(
+        &mut self,
+        buffer: BufReader<impl Read>,
+        policy: LineErrorPolicy,
+    ) -> Result<u64, SingerError> {
+        let mut skipped = 0;
         for line in buffer.lines() {
-            let line = line.expect("read input line");
-            self.process_line(&line).expect("process input line");
+            let line = line?;
+            if let Err(err) = self.process_line(&line) {
+                match policy {
+                    LineErrorPolicy::FailFast => return Err(err),
+                    LineErrorPolicy::SkipAndCount => skipped += 1,
+                }
+            }
         }
-        Ok(())
+        Ok(skipped)
     }
 
     /// Process a single Singer `RECORD` message.
@@ -83,10 +128,10 @@ pub trait MessageReader {
     fn process_record(
         &mut self,
         stream: String,
-        record: serde_json::Value,
+        record: Value,
         time_extracted: Option<String>,
         version: u64,
-    ) -> Result<(), serde_json::Error>;
+    ) -> Result<(), SingerError>;
 
     /// Process a single Singer `SCHEMA` message.
     ///
@@ -99,17 +144,17 @@ pub trait MessageReader {
     fn process_schema(
         &mut self,
         stream: String,
-        schema: serde_json::Value,
+        schema: Value,
         key_properties: Vec<String>,
         bookmark_properties: Vec<String>,
-    ) -> Result<(), serde_json::Error>;
+    ) -> Result<(), SingerError>;
 
     /// Process a single Singer `STATE` message.
     ///
     /// # Arguments
     ///
     /// * `value` - The state payload.
-    fn process_state(&mut self, value: serde_json::Value) -> Result<(), serde_json::Error>;
+    fn process_state(&mut self, value: Value) -> Result<(), SingerError>;
 
     /// Process a single Singer `ACTIVATE_VERSION` message.
     ///
@@ -117,11 +162,7 @@ pub trait MessageReader {
     ///
     /// * `stream` - The stream name.
     /// * `version` - The version of the stream.
-    fn process_activate_version(
-        &mut self,
-        stream: String,
-        version: u64,
-    ) -> Result<(), serde_json::Error>;
+    fn process_activate_version(&mut self, stream: String, version: u64) -> Result<(), SingerError>;
 
     /// Process a single Singer `BATCH` message.
     ///
@@ -135,5 +176,150 @@ pub trait MessageReader {
         stream: String,
         manifest: Vec<String>,
         encoding: BatchEncoding,
-    ) -> Result<(), serde_json::Error>;
+    ) -> Result<(), SingerError>;
+}
+
+/// Turn a failed `serde_json` parse of a line into a [`SingerError`], distinguishing
+/// an unrecognized `type` field (a message kind this version of the crate doesn't
+/// know about) from a generally malformed line.
+fn classify_parse_error(line: &str, err: serde_json::Error) -> SingerError {
+    let known_type = serde_json::from_str::<Value>(line)
+        .ok()
+        .and_then(|raw| raw.get("type").and_then(Value::as_str).map(str::to_string));
+
+    match known_type {
+        Some(type_str)
+            if !matches!(
+                type_str.as_str(),
+                "RECORD" | "SCHEMA" | "STATE" | "ACTIVATE_VERSION" | "BATCH"
+            ) =>
+        {
+            SingerError::UnknownMessageType(type_str)
+        }
+        _ => SingerError::Deserialize(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopReader;
+
+    impl MessageReader for NoopReader {
+        fn process_record(
+            &mut self,
+            _stream: String,
+            _record: Value,
+            _time_extracted: Option<String>,
+            _version: u64,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_schema(
+            &mut self,
+            _stream: String,
+            _schema: Value,
+            _key_properties: Vec<String>,
+            _bookmark_properties: Vec<String>,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_state(&mut self, _value: Value) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_activate_version(
+            &mut self,
+            _stream: String,
+            _version: u64,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_batch(
+            &mut self,
+            _stream: String,
+            _manifest: Vec<String>,
+            _encoding: BatchEncoding,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+    }
+
+    struct FailingReader;
+
+    impl MessageReader for FailingReader {
+        fn process_record(
+            &mut self,
+            _stream: String,
+            _record: Value,
+            _time_extracted: Option<String>,
+            _version: u64,
+        ) -> Result<(), SingerError> {
+            Err(SingerError::Validation("nope".to_string()))
+        }
+
+        fn process_schema(
+            &mut self,
+            _stream: String,
+            _schema: Value,
+            _key_properties: Vec<String>,
+            _bookmark_properties: Vec<String>,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_state(&mut self, _value: Value) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_activate_version(
+            &mut self,
+            _stream: String,
+            _version: u64,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_batch(
+            &mut self,
+            _stream: String,
+            _manifest: Vec<String>,
+            _encoding: BatchEncoding,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_unknown_message_type_is_classified() {
+        let mut reader = NoopReader;
+        let result = reader.process_line(r#"{"type": "UNKNOWN"}"#);
+        assert!(matches!(result, Err(SingerError::UnknownMessageType(t)) if t == "UNKNOWN"));
+    }
+
+    #[test]
+    fn test_fail_fast_aborts_on_first_error() {
+        let mut reader = FailingReader;
+        let input = "{\"type\": \"RECORD\", \"stream\": \"s\", \"record\": {}}\n\
+                      {\"type\": \"RECORD\", \"stream\": \"s\", \"record\": {}}\n";
+        let buffer = BufReader::new(input.as_bytes());
+        let result = reader.process_lines_with_policy(buffer, LineErrorPolicy::FailFast);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_skip_and_count_tallies_dropped_lines() {
+        let mut reader = FailingReader;
+        let input = "{\"type\": \"RECORD\", \"stream\": \"s\", \"record\": {}}\n\
+                      {\"type\": \"RECORD\", \"stream\": \"s\", \"record\": {}}\n";
+        let buffer = BufReader::new(input.as_bytes());
+        let skipped = reader
+            .process_lines_with_policy(buffer, LineErrorPolicy::SkipAndCount)
+            .unwrap();
+        assert_eq!(skipped, 2);
+    }
 }
@@ -0,0 +1,11 @@
+mod io;
+mod types;
+
+#[cfg(feature = "async")]
+mod async_io;
+
+pub use io::{write_message, LineErrorPolicy, MessageReader};
+pub use types::{BatchEncoding, Message};
+
+#[cfg(feature = "async")]
+pub use async_io::AsyncMessageReader;
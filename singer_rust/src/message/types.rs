@@ -10,6 +10,26 @@ pub struct BatchEncoding {
     compression: String,
 }
 
+impl BatchEncoding {
+    /// Build a `BatchEncoding`, e.g. `BatchEncoding::new("jsonl", "gzip")`.
+    pub fn new(format: impl Into<String>, compression: impl Into<String>) -> Self {
+        Self {
+            format: format.into(),
+            compression: compression.into(),
+        }
+    }
+
+    /// The batch file format, e.g. `jsonl`.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    /// The batch file compression, e.g. `gzip` or `none`.
+    pub fn compression(&self) -> &str {
+        &self.compression
+    }
+}
+
 /// A Singer message
 ///
 /// See the [Singer docs](https://github.com/singer-io/getting-started/blob/master/docs/SPEC.md#output).
@@ -95,6 +115,15 @@ pub enum Message {
 impl Message {
     /// Convert a Singer message to a JSON string.
     ///
+    /// This crate does not currently enable `serde_json`'s `arbitrary_precision` or
+    /// `preserve_order` features, so large integers and high-precision decimals
+    /// round-trip lossily through `f64` (anything past `f64`'s 53-bit mantissa, or
+    /// outside `i64`/`u64` range, loses precision), and object keys come back
+    /// sorted rather than in the order a tap emitted them. Preserving both exactly
+    /// would mean exposing a `json-precision` feature on this crate that forwards
+    /// to those two `serde_json` features; see [`current_precision_limits`] for
+    /// what that gap looks like today.
+    ///
     /// # Arguments
     ///
     /// * `message` - A Singer message.
@@ -121,6 +150,9 @@ impl Message {
 
     /// Convert a JSON string to a Singer message.
     ///
+    /// See [`Message::to_string`] for the current precision and key-order
+    /// limitations this carries.
+    ///
     /// # Arguments
     ///
     /// * `message` - A JSON string.
@@ -164,3 +196,45 @@ impl Message {
         serde_json::from_str(message)
     }
 }
+
+/// These document the precision/key-order gap called out on [`Message::to_string`],
+/// not a feature this crate delivers: there is no manifest in this tree to declare
+/// a `json-precision` feature and wire it to `serde_json`'s `arbitrary_precision`/
+/// `preserve_order`, so what's asserted below is today's lossy behavior, not the
+/// exact round-tripping a tap emitting large integers or ordered keys would want.
+#[cfg(test)]
+mod current_precision_limits {
+    use super::*;
+
+    #[test]
+    fn test_large_integer_loses_precision_through_f64() {
+        // 30 digits: overflows both i64/u64 and f64's 53-bit mantissa. With
+        // `arbitrary_precision` enabled this would round-trip exactly; today it
+        // comes back as a lossy `f64` approximation instead.
+        let raw = "123456789012345678901234567890";
+        let message = format!(
+            r#"{{"type":"RECORD","stream":"my_stream","record":{{"id":{raw}}},"version":1}}"#
+        );
+        let Message::RECORD { record, .. } = Message::from_string(&message).unwrap() else {
+            panic!("expected a RECORD message");
+        };
+        assert_ne!(record["id"].to_string(), raw);
+    }
+
+    #[test]
+    fn test_record_keys_do_not_keep_tap_emitted_order() {
+        // With `preserve_order` enabled this would come back `["z", "a", "m"]`;
+        // today `serde_json`'s default map sorts keys alphabetically instead.
+        let message = r#"{
+            "type": "RECORD",
+            "stream": "my_stream",
+            "record": {"z": 1, "a": 2, "m": 3},
+            "version": 1
+        }"#;
+        let Message::RECORD { record, .. } = Message::from_string(message).unwrap() else {
+            panic!("expected a RECORD message");
+        };
+        let keys: Vec<&String> = record.as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["a", "m", "z"]);
+    }
+}
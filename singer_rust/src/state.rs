@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::error::SingerError;
+use crate::message::MessageReader;
+
+#[derive(Debug, Error)]
+pub enum StateStoreError {
+    #[error("state store IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("state store serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[cfg(feature = "sled")]
+    #[error("sled error: {0}")]
+    Sled(#[from] sled::Error),
+}
+
+/// Deep-merge `incoming` onto `target` in place: nested objects are merged key by
+/// key, any other value (including arrays) is replaced outright. This mirrors how
+/// Singer taps emit incremental STATE deltas — later bookmarks refine earlier
+/// ones rather than replacing the whole document.
+pub fn deep_merge(target: &mut Value, incoming: &Value) {
+    match (target, incoming) {
+        (Value::Object(target_map), Value::Object(incoming_map)) => {
+            for (key, value) in incoming_map {
+                deep_merge(target_map.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (target, incoming) => *target = incoming.clone(),
+    }
+}
+
+/// Persists Singer STATE bookmarks, keyed by stream so partial/interrupted syncs can
+/// resume from where they left off.
+pub trait StateStore {
+    fn read(&self) -> Option<Value>;
+    fn write(&mut self, value: &Value) -> Result<(), StateStoreError>;
+    fn merge_bookmark(&mut self, stream: &str, key: &str, value: Value) -> Result<(), StateStoreError>;
+}
+
+/// Deep-merge every stream's bookmarks out of an incoming STATE message's `value`
+/// into `store`, one bookmark key at a time, so callers can delegate
+/// `process_state` straight to a configured [`StateStore`] instead of writing their
+/// own merge logic.
+pub fn merge_state(store: &mut dyn StateStore, value: &Value) -> Result<(), StateStoreError> {
+    let Some(bookmarks) = value.get("bookmarks").and_then(Value::as_object) else {
+        return Ok(());
+    };
+    for (stream, doc) in bookmarks {
+        let Some(fields) = doc.as_object() else {
+            continue;
+        };
+        for (key, value) in fields {
+            store.merge_bookmark(stream, key, value.clone())?;
+        }
+    }
+    Ok(())
+}
+
+/// An in-memory [`StateStore`], useful for tests or one-shot runs that don't need to
+/// survive a restart.
+#[derive(Debug, Default)]
+pub struct MemoryStateStore {
+    bookmarks: HashMap<String, Value>,
+}
+
+impl StateStore for MemoryStateStore {
+    fn read(&self) -> Option<Value> {
+        if self.bookmarks.is_empty() {
+            return None;
+        }
+        let bookmarks: serde_json::Map<String, Value> = self
+            .bookmarks
+            .iter()
+            .map(|(stream, doc)| (stream.clone(), doc.clone()))
+            .collect();
+        Some(json!({ "bookmarks": Value::Object(bookmarks) }))
+    }
+
+    fn write(&mut self, value: &Value) -> Result<(), StateStoreError> {
+        if let Some(bookmarks) = value.get("bookmarks").and_then(Value::as_object) {
+            for (stream, doc) in bookmarks {
+                self.bookmarks.insert(stream.clone(), doc.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_bookmark(
+        &mut self,
+        stream: &str,
+        key: &str,
+        value: Value,
+    ) -> Result<(), StateStoreError> {
+        let doc = self
+            .bookmarks
+            .entry(stream.to_string())
+            .or_insert_with(|| json!({}));
+        deep_merge(doc, &json!({ key: value }));
+        Ok(())
+    }
+}
+
+/// An embedded-database [`StateStore`] built on `sled`, keyed by stream name, so
+/// bookmarks survive a process restart.
+#[cfg(feature = "sled")]
+pub struct SledStateStore {
+    db: sled::Db,
+}
+
+#[cfg(feature = "sled")]
+impl SledStateStore {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, StateStoreError> {
+        Ok(Self {
+            db: sled::open(path)?,
+        })
+    }
+}
+
+#[cfg(feature = "sled")]
+impl StateStore for SledStateStore {
+    fn read(&self) -> Option<Value> {
+        let mut bookmarks = serde_json::Map::new();
+        for (key, bytes) in self.db.iter().flatten() {
+            let stream = String::from_utf8_lossy(&key).to_string();
+            if let Ok(doc) = serde_json::from_slice(&bytes) {
+                bookmarks.insert(stream, doc);
+            }
+        }
+        if bookmarks.is_empty() {
+            None
+        } else {
+            Some(json!({ "bookmarks": Value::Object(bookmarks) }))
+        }
+    }
+
+    fn write(&mut self, value: &Value) -> Result<(), StateStoreError> {
+        if let Some(bookmarks) = value.get("bookmarks").and_then(Value::as_object) {
+            for (stream, doc) in bookmarks {
+                self.db.insert(stream.as_bytes(), serde_json::to_vec(doc)?)?;
+            }
+        }
+        self.db.flush()?;
+        Ok(())
+    }
+
+    fn merge_bookmark(
+        &mut self,
+        stream: &str,
+        key: &str,
+        value: Value,
+    ) -> Result<(), StateStoreError> {
+        let mut doc = match self.db.get(stream.as_bytes())? {
+            Some(bytes) => serde_json::from_slice(&bytes)?,
+            None => json!({}),
+        };
+        deep_merge(&mut doc, &json!({ key: value }));
+        self.db.insert(stream.as_bytes(), serde_json::to_vec(&doc)?)?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+/// Opt-in extension of [`MessageReader`] that persists STATE messages to a
+/// [`StateStore`] instead of requiring every implementor to write its own
+/// bookmark-persistence logic.
+pub trait StatefulMessageReader: MessageReader {
+    type Store: StateStore;
+
+    fn state_store(&mut self) -> &mut Self::Store;
+
+    /// Deep-merge an incoming STATE message's bookmarks into the configured store.
+    /// Call this from `process_state` to delegate persistence entirely.
+    fn process_state_with_store(&mut self, value: Value) -> Result<(), SingerError> {
+        merge_state(self.state_store(), &value)
+            .map_err(|err| SingerError::Validation(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deep_merge_replaces_non_object_leaves() {
+        let mut target = json!({"updated_at": "2023-01-01T00:00:00Z"});
+        deep_merge(&mut target, &json!({"updated_at": "2023-01-02T00:00:00Z"}));
+        assert_eq!(target, json!({"updated_at": "2023-01-02T00:00:00Z"}));
+    }
+
+    #[test]
+    fn test_deep_merge_merges_nested_objects() {
+        let mut target = json!({"a": {"x": 1, "y": 2}});
+        deep_merge(&mut target, &json!({"a": {"y": 3, "z": 4}}));
+        assert_eq!(target, json!({"a": {"x": 1, "y": 3, "z": 4}}));
+    }
+
+    #[test]
+    fn test_memory_state_store_merge_bookmark() {
+        let mut store = MemoryStateStore::default();
+        store
+            .merge_bookmark("orders", "updated_at", json!("2023-01-01T00:00:00Z"))
+            .unwrap();
+        store
+            .merge_bookmark("orders", "offset", json!(100))
+            .unwrap();
+
+        assert_eq!(
+            store.read().unwrap(),
+            json!({"bookmarks": {"orders": {"updated_at": "2023-01-01T00:00:00Z", "offset": 100}}})
+        );
+    }
+
+    #[test]
+    fn test_merge_state_deep_merges_incoming_bookmarks() {
+        let mut store = MemoryStateStore::default();
+        store
+            .write(&json!({"bookmarks": {"orders": {"updated_at": "2023-01-01T00:00:00Z"}}}))
+            .unwrap();
+
+        merge_state(&mut store, &json!({"bookmarks": {"orders": {"offset": 100}}})).unwrap();
+
+        assert_eq!(
+            store.read().unwrap(),
+            json!({"bookmarks": {"orders": {"updated_at": "2023-01-01T00:00:00Z", "offset": 100}}})
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::error::SingerError;
+use crate::message::{BatchEncoding, MessageReader};
+
+/// A compiled JSON Schema, ready to validate `RECORD` payloads against.
+pub type CompiledSchema = jsonschema::JSONSchema;
+
+/// Wraps a [`MessageReader`] to validate every `RECORD` payload against the JSON
+/// Schema its stream declared in the most recent `SCHEMA` message, rejecting
+/// records that don't conform instead of handing them to the inner reader.
+///
+/// By default, streams that never receive a `SCHEMA` message are passed through
+/// unvalidated. Call [`ValidatingReader::require_schema`] to instead treat a
+/// `RECORD` with no known schema as a validation error — useful for taps where a
+/// `SCHEMA` before the first `RECORD` of a stream is a hard protocol requirement.
+pub struct ValidatingReader<R: MessageReader> {
+    inner: R,
+    schemas: HashMap<String, CompiledSchema>,
+    require_schema: bool,
+}
+
+impl<R: MessageReader> ValidatingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            schemas: HashMap::new(),
+            require_schema: false,
+        }
+    }
+
+    /// Treat a `RECORD` for a stream with no compiled schema as a validation error
+    /// rather than passing it through unchecked.
+    pub fn require_schema(mut self) -> Self {
+        self.require_schema = true;
+        self
+    }
+
+    fn validate(&self, stream: &str, record: &Value) -> Result<(), SingerError> {
+        match self.schemas.get(stream) {
+            Some(schema) => schema.validate(record).map_err(|errors| {
+                let detail = errors
+                    .map(|e| format!("{} (at {})", e, e.instance_path))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                SingerError::Validation(format!(
+                    "RECORD for stream {} failed schema validation: {}",
+                    stream, detail
+                ))
+            }),
+            None if self.require_schema => Err(SingerError::Validation(format!(
+                "RECORD for stream {} arrived before its SCHEMA",
+                stream
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R: MessageReader> MessageReader for ValidatingReader<R> {
+    fn process_record(
+        &mut self,
+        stream: String,
+        record: Value,
+        time_extracted: Option<String>,
+        version: u64,
+    ) -> Result<(), SingerError> {
+        self.validate(&stream, &record)?;
+        self.inner
+            .process_record(stream, record, time_extracted, version)
+    }
+
+    fn process_schema(
+        &mut self,
+        stream: String,
+        schema: Value,
+        key_properties: Vec<String>,
+        bookmark_properties: Vec<String>,
+    ) -> Result<(), SingerError> {
+        // A stream may redefine its SCHEMA mid-run (e.g. after a column is added);
+        // recompile and replace rather than keeping the stale validator around.
+        let compiled = CompiledSchema::compile(&schema).map_err(|e| {
+            SingerError::Validation(format!("invalid schema for {}: {}", stream, e))
+        })?;
+        self.schemas.insert(stream.clone(), compiled);
+        self.inner
+            .process_schema(stream, schema, key_properties, bookmark_properties)
+    }
+
+    fn process_state(&mut self, value: Value) -> Result<(), SingerError> {
+        self.inner.process_state(value)
+    }
+
+    fn process_activate_version(
+        &mut self,
+        stream: String,
+        version: u64,
+    ) -> Result<(), SingerError> {
+        self.inner.process_activate_version(stream, version)
+    }
+
+    fn process_batch(
+        &mut self,
+        stream: String,
+        manifest: Vec<String>,
+        encoding: BatchEncoding,
+    ) -> Result<(), SingerError> {
+        self.inner.process_batch(stream, manifest, encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct CountingReader {
+        records: u32,
+    }
+
+    impl MessageReader for CountingReader {
+        fn process_record(
+            &mut self,
+            _stream: String,
+            _record: Value,
+            _time_extracted: Option<String>,
+            _version: u64,
+        ) -> Result<(), SingerError> {
+            self.records += 1;
+            Ok(())
+        }
+
+        fn process_schema(
+            &mut self,
+            _stream: String,
+            _schema: Value,
+            _key_properties: Vec<String>,
+            _bookmark_properties: Vec<String>,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_state(&mut self, _value: Value) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_activate_version(
+            &mut self,
+            _stream: String,
+            _version: u64,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+
+        fn process_batch(
+            &mut self,
+            _stream: String,
+            _manifest: Vec<String>,
+            _encoding: BatchEncoding,
+        ) -> Result<(), SingerError> {
+            Ok(())
+        }
+    }
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {"id": {"type": "integer"}},
+            "required": ["id"]
+        })
+    }
+
+    #[test]
+    fn test_valid_record_passes_through() {
+        let mut reader = ValidatingReader::new(CountingReader::default());
+        reader
+            .process_schema("orders".to_string(), schema(), vec!["id".to_string()], vec![])
+            .unwrap();
+        reader
+            .process_record("orders".to_string(), json!({"id": 1}), None, 1)
+            .unwrap();
+        assert_eq!(reader.inner.records, 1);
+    }
+
+    #[test]
+    fn test_invalid_record_is_rejected() {
+        let mut reader = ValidatingReader::new(CountingReader::default());
+        reader
+            .process_schema("orders".to_string(), schema(), vec!["id".to_string()], vec![])
+            .unwrap();
+        let result = reader.process_record("orders".to_string(), json!({"id": "not-a-number"}), None, 1);
+        assert!(result.is_err());
+        assert_eq!(reader.inner.records, 0);
+    }
+
+    #[test]
+    fn test_record_for_unknown_stream_passes_through_by_default() {
+        let mut reader = ValidatingReader::new(CountingReader::default());
+        reader
+            .process_record("orders".to_string(), json!({"id": "not-a-number"}), None, 1)
+            .unwrap();
+        assert_eq!(reader.inner.records, 1);
+    }
+
+    #[test]
+    fn test_record_before_schema_errors_when_required() {
+        let mut reader = ValidatingReader::new(CountingReader::default()).require_schema();
+        let result = reader.process_record("orders".to_string(), json!({"id": 1}), None, 1);
+        assert!(result.is_err());
+        assert_eq!(reader.inner.records, 0);
+    }
+
+    #[test]
+    fn test_schema_redefinition_recompiles() {
+        let mut reader = ValidatingReader::new(CountingReader::default());
+        reader
+            .process_schema("orders".to_string(), schema(), vec!["id".to_string()], vec![])
+            .unwrap();
+        let looser_schema = json!({"type": "object", "properties": {"id": {"type": "string"}}});
+        reader
+            .process_schema("orders".to_string(), looser_schema, vec![], vec![])
+            .unwrap();
+        reader
+            .process_record("orders".to_string(), json!({"id": "abc"}), None, 1)
+            .unwrap();
+        assert_eq!(reader.inner.records, 1);
+    }
+}
@@ -4,17 +4,21 @@ use clap::Parser;
 use serde_json::to_string;
 
 use singer_rust::MessageReader;
-use singer_summarize::{cli, StatsReader};
+use singer_summarize::{cli, cli::LogFormat, StatsReader};
 
 pub fn main() {
-    let _args = cli::Args::parse();
+    let args = cli::Args::parse();
 
     let mut reader = StatsReader::new();
+    if args.log_format == LogFormat::Json {
+        reader = reader.with_json_logging();
+    }
     let buffer = io::BufReader::new(io::stdin());
     reader.process_lines(buffer).expect("valid messages");
 
-    let output = to_string(&reader.stats).expect("valid counts map");
+    let stats = reader.stats.lock().expect("stats mutex poisoned");
+    let output = to_string(&*stats).expect("valid counts map");
     eprintln!("{}", output);
 
-    println!("{}", to_string(&reader.stats.state.last_seen).unwrap())
+    println!("{}", to_string(&stats.state.last_seen).unwrap())
 }
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 /// Singer Summarize
 #[derive(Parser, Debug)]
@@ -7,4 +7,16 @@ pub struct Args {
     /// Config file
     #[clap(short, long)]
     pub config: Option<String>,
+
+    /// Log format for per-message progress output on stderr
+    #[clap(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// The existing behavior: a single stats summary at the end of the run.
+    Text,
+    /// One line-delimited JSON log record per observed Singer message.
+    Json,
 }
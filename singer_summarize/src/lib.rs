@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{json, Value};
 use singer_rust::message::{BatchEncoding, MessageReader};
+#[cfg(feature = "async")]
+use singer_rust::message::AsyncMessageReader;
+use singer_rust::log::{JsonLogEmitter, LogEmitter};
+use singer_rust::SingerError;
 
 pub mod cli;
 
@@ -50,13 +54,41 @@ impl Stats {
 }
 
 pub struct StatsReader {
-    pub stats: Stats,
+    /// Guarded by a `Mutex` (rather than requiring `&mut self`) so that the
+    /// `async` impl below can be fanned out across streams by
+    /// [`process_lines_with_backpressure`](singer_rust::message::AsyncMessageReader::process_lines_with_backpressure)
+    /// instead of serializing every message through one exclusive borrow.
+    pub stats: std::sync::Mutex<Stats>,
+    logger: Option<JsonLogEmitter>,
 }
 
 impl StatsReader {
     pub fn new() -> Self {
         Self {
-            stats: Stats::new(),
+            stats: std::sync::Mutex::new(Stats::new()),
+            logger: None,
+        }
+    }
+
+    /// Emit one JSON log record per observed Singer message to stderr, in addition to
+    /// the usual end-of-run stats summary.
+    pub fn with_json_logging(mut self) -> Self {
+        self.logger = Some(JsonLogEmitter);
+        self
+    }
+
+    /// Log a per-message progress line if JSON logging is enabled, carrying the
+    /// stream, message type and the stream's running count for that message type.
+    fn log_message(&self, stream: &str, msg_type: &str, running_count: u32) {
+        if let Some(logger) = &self.logger {
+            logger.emit(
+                "info",
+                &[
+                    ("stream", json!(stream)),
+                    ("msg_type", json!(msg_type)),
+                    ("running_count", json!(running_count)),
+                ],
+            );
         }
     }
 }
@@ -74,13 +106,12 @@ impl MessageReader for StatsReader {
         _record: Value,
         _time_extracted: Option<String>,
         _version: u64,
-    ) -> Result<(), serde_json::Error> {
-        let counter = self
-            .stats
-            .streams
-            .entry(stream)
-            .or_insert_with(Counter::new);
+    ) -> Result<(), SingerError> {
+        let stats = self.stats.get_mut().expect("stats mutex poisoned");
+        let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
         counter.record += 1;
+        let running_count = counter.record;
+        self.log_message(&stream, "RECORD", running_count);
         Ok(())
     }
 
@@ -90,13 +121,12 @@ impl MessageReader for StatsReader {
         _schema: Value,
         _key_properties: Vec<String>,
         _bookmark_properties: Vec<String>,
-    ) -> Result<(), serde_json::Error> {
-        let counter = self
-            .stats
-            .streams
-            .entry(stream)
-            .or_insert_with(Counter::new);
+    ) -> Result<(), SingerError> {
+        let stats = self.stats.get_mut().expect("stats mutex poisoned");
+        let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
         counter.schema += 1;
+        let running_count = counter.schema;
+        self.log_message(&stream, "SCHEMA", running_count);
         Ok(())
     }
 
@@ -104,13 +134,12 @@ impl MessageReader for StatsReader {
         &mut self,
         stream: String,
         _version: u64,
-    ) -> Result<(), serde_json::Error> {
-        let counter = self
-            .stats
-            .streams
-            .entry(stream)
-            .or_insert_with(Counter::new);
+    ) -> Result<(), SingerError> {
+        let stats = self.stats.get_mut().expect("stats mutex poisoned");
+        let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
         counter.activate_version += 1;
+        let running_count = counter.activate_version;
+        self.log_message(&stream, "ACTIVATE_VERSION", running_count);
         Ok(())
     }
 
@@ -119,19 +148,106 @@ impl MessageReader for StatsReader {
         stream: String,
         _manifest: Vec<String>,
         _encoding: BatchEncoding,
-    ) -> Result<(), serde_json::Error> {
-        let counter = self
-            .stats
-            .streams
-            .entry(stream)
-            .or_insert_with(Counter::new);
+    ) -> Result<(), SingerError> {
+        let stats = self.stats.get_mut().expect("stats mutex poisoned");
+        let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
         counter.batch += 1;
+        let running_count = counter.batch;
+        self.log_message(&stream, "BATCH", running_count);
+        Ok(())
+    }
+
+    fn process_state(&mut self, value: Value) -> Result<(), SingerError> {
+        let stats = self.stats.get_mut().expect("stats mutex poisoned");
+        stats.state.count += 1;
+        stats.state.last_seen = value.clone();
+        let running_count = stats.state.count;
+        self.log_message("", "STATE", running_count);
+        Ok(())
+    }
+}
+
+/// Async counterpart of the [`MessageReader`] impl above, for embedding
+/// `singer-summarize`'s stats collection in a Tokio-based service. Takes `&self`
+/// rather than `&mut self`, per [`AsyncMessageReader`], so a bounded-channel
+/// pipeline can fan these out across streams; each handler only holds the
+/// `stats` mutex for the few field updates it needs.
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl AsyncMessageReader for StatsReader {
+    async fn process_record(
+        &self,
+        stream: String,
+        _record: Value,
+        _time_extracted: Option<String>,
+        _version: u64,
+    ) -> Result<(), SingerError> {
+        let running_count = {
+            let mut stats = self.stats.lock().expect("stats mutex poisoned");
+            let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
+            counter.record += 1;
+            counter.record
+        };
+        self.log_message(&stream, "RECORD", running_count);
+        Ok(())
+    }
+
+    async fn process_schema(
+        &self,
+        stream: String,
+        _schema: Value,
+        _key_properties: Vec<String>,
+        _bookmark_properties: Vec<String>,
+    ) -> Result<(), SingerError> {
+        let running_count = {
+            let mut stats = self.stats.lock().expect("stats mutex poisoned");
+            let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
+            counter.schema += 1;
+            counter.schema
+        };
+        self.log_message(&stream, "SCHEMA", running_count);
+        Ok(())
+    }
+
+    async fn process_state(&self, value: Value) -> Result<(), SingerError> {
+        let running_count = {
+            let mut stats = self.stats.lock().expect("stats mutex poisoned");
+            stats.state.count += 1;
+            stats.state.last_seen = value;
+            stats.state.count
+        };
+        self.log_message("", "STATE", running_count);
         Ok(())
     }
 
-    fn process_state(&mut self, value: Value) -> Result<(), serde_json::Error> {
-        self.stats.state.count += 1;
-        self.stats.state.last_seen = value;
+    async fn process_activate_version(
+        &self,
+        stream: String,
+        _version: u64,
+    ) -> Result<(), SingerError> {
+        let running_count = {
+            let mut stats = self.stats.lock().expect("stats mutex poisoned");
+            let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
+            counter.activate_version += 1;
+            counter.activate_version
+        };
+        self.log_message(&stream, "ACTIVATE_VERSION", running_count);
+        Ok(())
+    }
+
+    async fn process_batch(
+        &self,
+        stream: String,
+        _manifest: Vec<String>,
+        _encoding: BatchEncoding,
+    ) -> Result<(), SingerError> {
+        let running_count = {
+            let mut stats = self.stats.lock().expect("stats mutex poisoned");
+            let counter = stats.streams.entry(stream.clone()).or_insert_with(Counter::new);
+            counter.batch += 1;
+            counter.batch
+        };
+        self.log_message(&stream, "BATCH", running_count);
         Ok(())
     }
 }
@@ -153,12 +269,13 @@ mod tests {
         let buffer = std::io::BufReader::new(file);
         reader.process_lines(buffer).unwrap();
 
-        assert_eq!(reader.stats.streams.len(), 1);
-        assert_eq!(reader.stats.streams["example"].schema, 1);
-        assert_eq!(reader.stats.streams["example"].record, 2);
-        assert_eq!(reader.stats.state.count, 1);
+        let stats = reader.stats.lock().unwrap();
+        assert_eq!(stats.streams.len(), 1);
+        assert_eq!(stats.streams["example"].schema, 1);
+        assert_eq!(stats.streams["example"].record, 2);
+        assert_eq!(stats.state.count, 1);
         assert_eq!(
-            reader.stats.state.last_seen,
+            stats.state.last_seen,
             json!({"bookmarks": {"example": {"updated_at": "2023-04-10T00:00:10Z"}}})
         );
     }